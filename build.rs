@@ -0,0 +1,66 @@
+//! Generates `tls.x`, the linker script fragment that reserves one
+//! `TLS_CORE_n` region per core for `src/lib.rs`'s `__pre_init` hook to
+//! populate.
+//!
+//! Downstream crates used to have to hand-copy the `.tdata`/`.tbss`/
+//! `.tls_state` `SECTIONS` blocks and the `TLS_CORE_0`/`TLS_CORE_1` symbol
+//! definitions into their own `memory.x`, which silently broke if that copy
+//! drifted from the layout `tls_pre_init_hook` expects. Now they only need
+//! `INCLUDE tls.x`, and this is the one place that layout is defined.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Number of `TLS_CORE_n` regions to reserve. Keep in sync with `NUM_CORES`
+/// in `src/lib.rs`.
+const NUM_CORES: usize = 2;
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    let mut slots = String::new();
+    for core in 0..NUM_CORES {
+        slots.push_str(&format!(
+            "    . = ALIGN(4);\n    TLS_CORE_{core} = .;\n    \
+             . += (__tdata_end - __tdata_start) + (__tbss_end - __tbss_start);\n"
+        ));
+    }
+
+    // `.tdata` and `.tbss` form a single template: the compiler numbers a
+    // thread-local's offset from `__tdata_start`, counting straight through
+    // from `.tdata` into `.tbss` as if they were one section, and
+    // `tls_pre_init_hook`/`riscv_init_tp` rely on that same layout when they
+    // copy `.tdata` into a `TLS_CORE_n` region and then zero the next
+    // `bsslen` bytes after it. So both sections must share one contiguous
+    // VMA range -- they go in `FLASH` together, even though `.tbss` (being
+    // `NOLOAD`) contributes no actual bytes there; only `.tls_state`, the
+    // real per-core storage the hooks copy *into*, needs `RAM`.
+    let tls_x = format!(
+        "SECTIONS {{\n\
+         \x20 .tdata :\n\
+         \x20 {{\n\
+         \x20   __tdata_start = .;\n\
+         \x20   *(.tdata .tdata.*);\n\
+         \x20   __tdata_end = .;\n\
+         \x20 }} > FLASH\n\
+         \n\
+         \x20 .tbss (NOLOAD) :\n\
+         \x20 {{\n\
+         \x20   __tbss_start = .;\n\
+         \x20   *(.tbss .tbss.*);\n\
+         \x20   __tbss_end = .;\n\
+         \x20 }} > FLASH\n\
+         \n\
+         \x20 .tls_state (NOLOAD) :\n\
+         \x20 {{\n\
+         {slots}\
+         \x20 }} > RAM\n\
+         }} INSERT AFTER .bss;\n"
+    );
+
+    fs::write(out_dir.join("tls.x"), tls_x).expect("failed to write tls.x");
+
+    println!("cargo:rustc-link-search={}", out_dir.display());
+    println!("cargo:rerun-if-changed=build.rs");
+}
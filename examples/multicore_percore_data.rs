@@ -62,10 +62,13 @@ const CORE1_DELAY: u32 = 1_000_000 / CORE1_FREQ;
 /// To get the same for Core 1, we would need to compile everything separately
 /// and modify the linker file for both programs, and that's quite annoying.
 /// So instead, core1.spawn takes a [usize] which gets used for the stack.
-/// NOTE: We use the `Stack` struct here to ensure that it has 32-byte
-/// alignment, which allows the stack guard to take up the least amount of
-/// usable RAM.
-static mut CORE1_STACK: Stack<4096> = Stack::new();
+/// NOTE: We wrap the `Stack` struct to align it to 256 bytes (rather than
+/// `Stack`'s own 32-byte alignment), since that's the minimum MPU region
+/// size on the Cortex-M0+'s ARMv6-M MPU, and `arm_core_stack_guard` needs
+/// its `base` aligned to the guard region size.
+#[repr(align(256))]
+struct Core1Stack(Stack<4096>);
+static mut CORE1_STACK: Core1Stack = Core1Stack(Stack::new());
 
 /// State for the blinker
 struct BlinkState {
@@ -135,7 +138,7 @@ fn main() -> ! {
     let cores = mc.cores();
     let core1 = &mut cores[1];
     core1
-        .spawn(unsafe { &mut CORE1_STACK.mem }, move || {
+        .spawn(unsafe { &mut CORE1_STACK.0.mem }, move || {
             // Get the second core's copy of the `CorePeripherals`, which are per-core.
             // Unfortunately, `cortex-m` doesn't support this properly right now,
             // so we have to use `steal`.
@@ -143,6 +146,13 @@ fn main() -> ! {
             // Set up the delay for the second core.
             let delay = Delay::new(core.SYST, sys_freq);
 
+            // Guard the low end of core 1's stack, so an overflow faults
+            // instead of silently corrupting whatever's below it.
+            rp2040_multicore_per_cpu::arm_core_stack_guard(
+                unsafe { CORE1_STACK.0.mem.as_ptr() } as u32,
+                unsafe { core::mem::size_of_val(&CORE1_STACK.0.mem) } as u32,
+            );
+
             STATE.borrow_mut().replace(BlinkState {
                 led: led2.into_dyn_pin(),
                 delay,
@@ -0,0 +1,175 @@
+//! # Multicore Blinking Example (stable Rust)
+//!
+//! Identical to `multicore_percore_data.rs`, except the per-core blinker
+//! state is stored in a [`CoreLocal`][rp2040_multicore_per_cpu::CoreLocal]
+//! instead of a `#[thread_local]` static, so this example builds on stable
+//! Rust.
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use cortex_m::delay::Delay;
+
+use hal::clocks::Clock;
+use hal::gpio::{DynPinId, FunctionSio, Pin, Pins, PullDown, SioOutput};
+use hal::multicore::{Multicore, Stack};
+use hal::sio::Sio;
+// Ensure we halt the program on panic (if we don't mention this crate it won't
+// be linked)
+use panic_halt as _;
+
+use rp2040_multicore_per_cpu::core_local;
+
+// Alias for our HAL crate
+use rp2040_hal as hal;
+
+// A shorter alias for the Peripheral Access Crate, which provides low-level
+// register access
+use hal::pac;
+
+// Some traits we need
+use embedded_hal::digital::StatefulOutputPin;
+
+/// The linker will place this boot block at the start of our program image. We
+/// need this to help the ROM bootloader get our code up and running.
+/// Note: This boot block is not necessary when using a rp-hal based BSP
+/// as the BSPs already perform this step.
+#[link_section = ".boot2"]
+#[used]
+pub static BOOT2: [u8; 256] = rp2040_boot2::BOOT_LOADER_GENERIC_03H;
+
+/// External high-speed crystal on the Raspberry Pi Pico board is 12 MHz. Adjust
+/// if your board has a different frequency
+const XTAL_FREQ_HZ: u32 = 12_000_000u32;
+
+/// The frequency at which core 0 will blink its LED (Hz).
+const CORE0_FREQ: u32 = 3;
+/// The frequency at which core 1 will blink its LED (Hz).
+const CORE1_FREQ: u32 = 4;
+/// The delay between each toggle of core 0's LED (us).
+const CORE0_DELAY: u32 = 1_000_000 / CORE0_FREQ;
+/// The delay between each toggle of core 1's LED (us).
+const CORE1_DELAY: u32 = 1_000_000 / CORE1_FREQ;
+
+/// Stack for core 1, see the note in `multicore_percore_data.rs` -- wrapped
+/// to 256-byte alignment so `arm_core_stack_guard`'s `base` requirement is
+/// met.
+#[repr(align(256))]
+struct Core1Stack(Stack<4096>);
+static mut CORE1_STACK: Core1Stack = Core1Stack(Stack::new());
+
+/// State for the blinker
+struct BlinkState {
+    led: Pin<DynPinId, FunctionSio<SioOutput>, PullDown>,
+    delay: Delay,
+    delay_time: u32,
+}
+
+core_local! {
+    /// Per core blinker state
+    static STATE: RefCell<Option<BlinkState>> = RefCell::new(None);
+}
+
+/// Blink which ever LED with whatever delay, according to the per-core state.
+fn blinker() -> ! {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let BlinkState {
+            led,
+            delay,
+            delay_time,
+        } = state.as_mut().unwrap();
+        loop {
+            led.toggle().unwrap();
+            delay.delay_us(*delay_time);
+        }
+    })
+}
+
+/// Entry point to our bare-metal application.
+///
+/// The `#[rp2040_hal::entry]` macro ensures the Cortex-M start-up code calls this function
+/// as soon as all global variables and the spinlock are initialised.
+#[rp2040_hal::entry]
+fn main() -> ! {
+    // Grab our singleton objects
+    let mut pac = pac::Peripherals::take().unwrap();
+    let core = pac::CorePeripherals::take().unwrap();
+
+    // Set up the watchdog driver - needed by the clock setup code
+    let mut watchdog = hal::watchdog::Watchdog::new(pac.WATCHDOG);
+
+    // Configure the clocks
+    let clocks = hal::clocks::init_clocks_and_plls(
+        XTAL_FREQ_HZ,
+        pac.XOSC,
+        pac.CLOCKS,
+        pac.PLL_SYS,
+        pac.PLL_USB,
+        &mut pac.RESETS,
+        &mut watchdog,
+    )
+    .unwrap();
+
+    let sys_freq = clocks.system_clock.freq().to_Hz();
+
+    // Set up the GPIO pins
+    let mut sio = Sio::new(pac.SIO);
+    let pins = Pins::new(
+        pac.IO_BANK0,
+        pac.PADS_BANK0,
+        sio.gpio_bank0,
+        &mut pac.RESETS,
+    );
+    let led1 = pins.gpio2.into_push_pull_output();
+    let led2 = pins.gpio3.into_push_pull_output();
+
+    // Start up the second core to blink the second LED
+    let mut mc = Multicore::new(&mut pac.PSM, &mut pac.PPB, &mut sio.fifo);
+    let cores = mc.cores();
+    let core1 = &mut cores[1];
+    core1
+        .spawn(unsafe { &mut CORE1_STACK.0.mem }, move || {
+            // Get the second core's copy of the `CorePeripherals`, which are per-core.
+            // Unfortunately, `cortex-m` doesn't support this properly right now,
+            // so we have to use `steal`.
+            let core = unsafe { pac::CorePeripherals::steal() };
+            // Set up the delay for the second core.
+            let delay = Delay::new(core.SYST, sys_freq);
+
+            // Guard the low end of core 1's stack, so an overflow faults
+            // instead of silently corrupting whatever's below it.
+            rp2040_multicore_per_cpu::arm_core_stack_guard(
+                unsafe { CORE1_STACK.0.mem.as_ptr() } as u32,
+                unsafe { core::mem::size_of_val(&CORE1_STACK.0.mem) } as u32,
+            );
+
+            STATE.with(|state| {
+                state.borrow_mut().replace(BlinkState {
+                    led: led2.into_dyn_pin(),
+                    delay,
+                    delay_time: CORE1_DELAY,
+                });
+            });
+
+            // Blink the second LED.
+            blinker();
+        })
+        .unwrap();
+
+    // Set up the delay for the first core.
+    let delay = Delay::new(core.SYST, sys_freq);
+
+    // Blink the first LED.
+    STATE.with(|state| {
+        state.borrow_mut().replace(BlinkState {
+            led: led1.into_dyn_pin(),
+            delay,
+            delay_time: CORE0_DELAY,
+        });
+    });
+    blinker();
+}
+
+// End of file
@@ -0,0 +1,83 @@
+//! Stable-Rust per-core storage.
+//!
+//! [`CoreLocal`] gives the same "one value per core" behaviour as the
+//! `#[thread_local]` statics documented at the crate root, but without
+//! requiring the unstable `thread_local` feature, any `memory.x` changes, or
+//! the `__pre_init` hook. It does this by simply keeping one copy of `T` per
+//! core inline and picking the right one by reading the SIO CPUID register,
+//! the same register read by `__aeabi_read_tp` for the `#[thread_local]`
+//! path.
+//!
+//! That covers `CoreLocal` itself, not the crate as a whole: on Arm builds
+//! the crate always installs its `__pre_init` hook, which copies into the
+//! `TLS_CORE_n` regions, so linking it still needs `INCLUDE tls.x` in
+//! `memory.x` and still leaves `__pre_init` unavailable for other uses.
+
+/// Address of the SIO `CPUID` register: reads as 0 on core 0 and 1 on core 1.
+const SIO_CPUID: *const u32 = 0xd000_0000 as *const u32;
+
+/// Returns the index (0 or 1) of the core currently executing.
+#[inline]
+fn core_id() -> usize {
+    // Safety: SIO_CPUID is always mapped and readable, on both cores, at any
+    // point during program execution.
+    unsafe { core::ptr::read_volatile(SIO_CPUID) as usize }
+}
+
+/// A value that has an independent copy for each of the RP2040's two cores.
+///
+/// Unlike a `#[thread_local]` static, this works on stable Rust and needs no
+/// linker script support: the two copies simply live inline in the
+/// `CoreLocal` itself, and [`core_id`] picks which one a given call sees.
+///
+/// ```rust,ignore
+/// # use rp2040_multicore_per_cpu::CoreLocal;
+/// static COUNTER: CoreLocal<u32> = CoreLocal::new(0, 0);
+/// COUNTER.with(|c| assert_eq!(*c, 0));
+/// ```
+pub struct CoreLocal<T> {
+    values: [T; 2],
+}
+
+impl<T> CoreLocal<T> {
+    /// Creates a new `CoreLocal`, with `core0` as the initial value on core 0
+    /// and `core1` as the initial value on core 1.
+    pub const fn new(core0: T, core1: T) -> Self {
+        Self {
+            values: [core0, core1],
+        }
+    }
+
+    /// Returns a reference to the calling core's value.
+    pub fn get(&self) -> &T {
+        &self.values[core_id()]
+    }
+
+    /// Runs `f` with a reference to the calling core's value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(self.get())
+    }
+}
+
+// Safety: the two cores never observe the same slot of `values`, so sharing
+// a `CoreLocal<T>` reference between cores is equivalent to each core owning
+// its own `T`.
+unsafe impl<T: Send> Sync for CoreLocal<T> {}
+
+/// Declares a [`CoreLocal`] static, mirroring the `#[thread_local]` example
+/// at the crate root but usable on stable Rust.
+///
+/// ```rust,ignore
+/// # use rp2040_multicore_per_cpu::core_local;
+/// core_local! {
+///     static MY_COUNTER: u32 = 0;
+/// }
+/// MY_COUNTER.with(|c| assert_eq!(*c, 0));
+/// ```
+#[macro_export]
+macro_rules! core_local {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::CoreLocal<$ty> = $crate::CoreLocal::new($init, $init);
+    };
+}
@@ -0,0 +1,112 @@
+//! Per-core heap allocator.
+//!
+//! [`PerCoreHeap`] gives each core its own heap, built out of a
+//! [`CoreLocal`] of [`linked_list_allocator::Heap`], so the two heaps never
+//! need to know about each other's free lists or bookkeeping. Each core
+//! calls [`init_this_core`][PerCoreHeap::init_this_core] from its own entry
+//! point to hand the allocator its own region of memory.
+//!
+//! Since the two heaps are entirely disjoint, there's no need for a lock
+//! that's shared between cores: `alloc`/`dealloc` only need to keep this
+//! core's own interrupt handlers out of this core's heap, which is a plain
+//! `cortex_m::interrupt::free` (masks only the calling core's IRQs) around a
+//! `RefCell`, not the `critical_section` that `embedded_alloc::Heap` uses --
+//! on RP2040 that takes a hardware spinlock shared between both cores, which
+//! would serialize the two heaps against each other for no reason.
+//!
+//! The one invariant this doesn't get for free from [`CoreLocal`] is that a
+//! block allocated on one core must be freed on the same core: the pointer
+//! alone doesn't say which heap it came from. In debug builds,
+//! [`dealloc`][PerCoreHeap::dealloc] checks the pointer against the calling
+//! core's heap range and panics if it's out of bounds, which catches the
+//! common case of freeing on the wrong core.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::{Cell, RefCell};
+use core::ptr::NonNull;
+
+use cortex_m::interrupt::{self, Mutex};
+use linked_list_allocator::Heap;
+
+use crate::CoreLocal;
+
+/// A [`GlobalAlloc`] that gives each core an independent heap.
+///
+/// ```rust,ignore
+/// #[global_allocator]
+/// static HEAP: PerCoreHeap = PerCoreHeap::empty();
+///
+/// fn core_entry(start: usize, size: usize) {
+///     unsafe { HEAP.init_this_core(start, size) };
+///     // `alloc` now works on this core, using its own heap.
+/// }
+/// ```
+pub struct PerCoreHeap {
+    heaps: CoreLocal<Mutex<RefCell<Heap>>>,
+    // (heap_start, heap_end), used only to sanity-check `dealloc` in debug
+    // builds.
+    bounds: CoreLocal<Cell<(usize, usize)>>,
+}
+
+impl PerCoreHeap {
+    /// Creates a new `PerCoreHeap` with no memory. Each core must call
+    /// [`init_this_core`][Self::init_this_core] before it allocates.
+    pub const fn empty() -> Self {
+        Self {
+            heaps: CoreLocal::new(
+                Mutex::new(RefCell::new(Heap::empty())),
+                Mutex::new(RefCell::new(Heap::empty())),
+            ),
+            bounds: CoreLocal::new(Cell::new((0, 0)), Cell::new((0, 0))),
+        }
+    }
+
+    /// Gives the calling core `size` bytes of heap starting at `start`.
+    ///
+    /// Must be called once, by each core, before that core makes any
+    /// allocations. `start..start + size` must describe memory that's free
+    /// for the rest of the program's execution and not used for anything
+    /// else (in particular, not the other core's heap).
+    pub unsafe fn init_this_core(&self, start: usize, size: usize) {
+        interrupt::free(|cs| {
+            self.heaps
+                .get()
+                .borrow(cs)
+                .borrow_mut()
+                .init(start as *mut u8, size)
+        });
+        self.bounds.get().set((start, start + size));
+    }
+}
+
+unsafe impl GlobalAlloc for PerCoreHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        interrupt::free(|cs| {
+            self.heaps
+                .get()
+                .borrow(cs)
+                .borrow_mut()
+                .allocate_first_fit(layout)
+                .map_or(core::ptr::null_mut(), |ptr| ptr.as_ptr())
+        })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let (start, end) = self.bounds.get().get();
+        debug_assert!(
+            (ptr as usize) >= start && (ptr as usize) < end,
+            "PerCoreHeap::dealloc: pointer {:p} is not in this core's heap \
+             ({:#x}..{:#x}) -- was it allocated on the other core?",
+            ptr,
+            start,
+            end,
+        );
+        interrupt::free(|cs| {
+            self.heaps
+                .get()
+                .borrow(cs)
+                .borrow_mut()
+                .deallocate(NonNull::new_unchecked(ptr), layout)
+        });
+    }
+}
@@ -29,7 +29,9 @@
 //!
 //! These core-local variables are initialized on program startup and retain
 //! their value from there on, even between invocations of
-//! [`Core::spawn`][spawn].
+//! [`Core::spawn`][spawn]. If you want a core's `#[thread_local]` statics
+//! back to their startup values when you respawn it, call
+//! [`reset_core_tls`] just before the respawning `Core::spawn` call.
 //!
 //! If the variables are zero-initialized then they will be reserved space in
 //! the `.tbss` section in the executable, and then space in `.bss` for each
@@ -40,9 +42,10 @@
 //!
 //! ## Build setup
 //!
-//! Note that this requires some setup in the linker script to allocate space
-//! for the static data. See memory.x for details. You also need to make you
-//! explicitly depend on this crate with `extern crate
+//! This crate's `build.rs` generates `tls.x`, a linker script fragment that
+//! allocates space for the per-core static data, and adds it to the linker
+//! search path; add `INCLUDE tls.x` to your `memory.x` to pull it in. You
+//! also need to make you explicitly depend on this crate with `extern crate
 //! rp2040_multicore_per_cpu;`. This crate has no Rust API of its own, but must
 //! still be included in the linker line to ensure the `__aeabi_read_tp`
 //! function is defined.
@@ -51,6 +54,53 @@
 //! per-core state at reset time, making it unavailable for other uses (this is
 //! rare however).
 //!
+//! ## Stable Rust
+//!
+//! If you can't use nightly, [`CoreLocal`] provides the same per-core
+//! storage on stable Rust, at the cost of storing both cores' copies inline
+//! (so `size_of::<CoreLocal<T>>() == 2 * size_of::<T>()`) instead of relying
+//! on the linker:
+//!
+//! ```rust,ignore
+//! use rp2040_multicore_per_cpu::core_local;
+//!
+//! # use core::cell::RefCell;
+//! core_local! {
+//!     static MY_COUNTER: RefCell<usize> = RefCell::new(0);
+//! }
+//!
+//! fn next_id() -> usize {
+//!     MY_COUNTER.with(|c| c.replace_with(|c| *c + 1))
+//! }
+//! ```
+//!
+//! ## Per-core heap
+//!
+//! [`PerCoreHeap`] extends the same idea to `alloc`: each core gets its own
+//! heap, so one core's allocation pattern can't fragment or corrupt the
+//! other's, as long as each core only frees memory it allocated itself.
+//! Since the two heaps never touch each other, allocating doesn't need a
+//! lock shared between cores either -- see its docs for details.
+//!
+//! ## Stack guard
+//!
+//! [`arm_core_stack_guard`] programs the calling core's MPU with a
+//! no-access guard region at the low end of its stack, so a stack overflow
+//! faults instead of silently corrupting whatever's just below it. Call it
+//! once from each core's entry point; see its docs for details. This is an
+//! Arm-only feature: there's no MPU to program on the RISC-V build.
+//!
+//! ## Chip and architecture support
+//!
+//! RP2040 (Cortex-M0+) is the default. Enable the `rp2350` Cargo feature
+//! for RP2350 (Pico 2) instead, where the target you build for picks Arm
+//! (Cortex-M33) or RISC-V (Hazard3): the Arm build works exactly like
+//! RP2040, with its own `__aeabi_read_tp`; the RISC-V build has no
+//! equivalent runtime hook, so each core must call `riscv_init_tp` once
+//! from its own entry point instead, which also takes care of that core's
+//! `.tdata`/`.tbss` copy-init since there's no `__pre_init`-style hook to
+//! do it for them.
+//!
 //! [multicore]:
 //!     https://docs.rs/rp2040-hal/latest/rp2040_hal/multicore/index.html
 //! [unstable]:
@@ -59,47 +109,47 @@
 //!     https://docs.rs/rp2040-hal/latest/rp2040_hal/multicore/struct.Core.html#method.spawn
 #![no_std]
 
-use core::arch::global_asm;
+mod core_local;
+pub use core_local::CoreLocal;
 
-extern "C" {
-    static mut TLS_CORE_0: u8;
-    static mut TLS_CORE_1: u8;
-}
+mod heap;
+pub use heap::PerCoreHeap;
 
-// Define `__aeabi_read_tp` called by the compiler to get access to
-// thread-local storage.
-global_asm! {
-    ".pushsection .text.__aeabi_read_tp",
-    ".align 4",
-    ".p2align 4,,15",
-    ".global __aeabi_read_tp",
-    ".type __aeabi_read_tp,%function",
+// The stack guard is programmed through the Arm MPU, which doesn't exist
+// (and `cortex_m::peripheral::MPU::PTR` doesn't point at anything sane) on
+// the RISC-V (rp2350, target_arch = "riscv32") build.
+#[cfg(target_arch = "arm")]
+mod stack_guard;
+#[cfg(target_arch = "arm")]
+pub use stack_guard::{arm_core_stack_guard, core_stack_guard_bounds};
 
-    "__aeabi_read_tp:",
-    "    ldr r0, =0xd0000000",      // Load SIO CPUID addr
-    "    ldr r0, [r0]",             // Load CPUID
-    "    cmp r0, #0",               // Check core 0
-    "    ldr r0, ={core_0}",        // Set TLS_CORE_0
-    "    beq 1f",                   // skip if done
-    "    ldr r0, ={core_1}",        // Set TLS_CORE_1
-    "1:  bx lr",
+mod tp;
+#[cfg(all(feature = "rp2350", target_arch = "riscv32"))]
+pub use tp::riscv_init_tp;
 
-    ".popsection",
-    core_0 = sym TLS_CORE_0,
-    core_1 = sym TLS_CORE_1,
+extern "C" {
+    pub(crate) static mut TLS_CORE_0: u8;
+    pub(crate) static mut TLS_CORE_1: u8;
 }
 
+/// Number of cores this chip has. Bumping this to support a chip with more
+/// cores also means adding the matching `TLS_CORE_n` symbols, here and in
+/// the linker script (`NUM_CORES` in `build.rs`), to the slot list in
+/// `tls_image::core_tls_slot`, and to the per-core selection in
+/// `__aeabi_read_tp`/`riscv_init_tp`.
+pub(crate) const NUM_CORES: usize = 2;
+
+mod tls_image;
+pub use tls_image::reset_core_tls;
+
+// `cortex-m-rt`'s `__pre_init` hook only exists on Cortex-M; on RISC-V,
+// `riscv_init_tp` does this same copy-init itself, once per core, since
+// there's no equivalent hook to run it for them.
+//
 // This must be pub for linkage but isn't a public API.
+#[cfg(target_arch = "arm")]
 mod inner {
-    use super::{TLS_CORE_0, TLS_CORE_1};
-    use core::ptr::{addr_of, addr_of_mut};
-
-    extern "C" {
-        static __tdata_start: u8;
-        static __tdata_end: u8;
-        static __tbss_start: u8;
-        static __tbss_end: u8;
-    }
+    use super::NUM_CORES;
 
     /// Intercept __pre_init to hook into the startup code to copy the tdata into
     /// TLS_CORE_0/1. TLS_CORE_0/1 are outside of the regular .bss segment, so we
@@ -110,11 +160,10 @@ mod inner {
     /// Assumes a stack has been set up.
     #[cortex_m_rt::pre_init]
     unsafe fn tls_pre_init_hook() {
-        for dst in [addr_of_mut!(TLS_CORE_0), addr_of_mut!(TLS_CORE_1)] {
-            let datalen = addr_of!(__tdata_end).offset_from(addr_of!(__tdata_start)) as usize;
-            core::ptr::copy(addr_of!(__tdata_start), dst, datalen);
-            let bsslen = addr_of!(__tbss_end).offset_from(addr_of!(__tbss_start)) as usize;
-            dst.add(datalen).write_bytes(0, bsslen);
+        // NUM_CORES slots, one per TLS_CORE_n symbol.
+        debug_assert_eq!(NUM_CORES, 2);
+        for core in 0..NUM_CORES {
+            crate::tls_image::reset_core_tls(core);
         }
     }
 }
@@ -0,0 +1,91 @@
+//! Per-core MPU stack guard.
+//!
+//! A shared guard region wouldn't make sense here: the MPU is per-core on
+//! RP2040, so each core's guard can only ever protect that core's own
+//! stack, and each core must program its own MPU independently (there's no
+//! cross-core aliasing hazard to worry about, but also no way to set one
+//! up on the other core's behalf).
+
+use core::cell::Cell;
+
+use cortex_m::peripheral::MPU;
+
+use crate::CoreLocal;
+
+/// The calling core's stack guard bounds, recorded so they can be inspected
+/// (or reprogrammed) later without re-deriving them.
+static STACK_BOUNDS: CoreLocal<Cell<Option<(u32, u32)>>> =
+    CoreLocal::new(Cell::new(None), Cell::new(None));
+
+/// MPU region number dedicated to the stack guard. Only one is needed,
+/// since each core only ever programs its own MPU.
+const GUARD_REGION: u32 = 7;
+
+/// Minimum size of the guard region, in bytes. MPU regions must be a power
+/// of two, aligned to their own size, and ARMv6-M (Cortex-M0+, as used on
+/// RP2040) requires at least 256 bytes -- smaller SIZE encodings are below
+/// the architectural minimum and are ignored by the hardware. 256 bytes of
+/// the stack are sacrificed to the guard rather than used for storage.
+///
+/// [hal]: https://docs.rs/rp2040-hal/latest/rp2040_hal/multicore/struct.Stack.html
+const GUARD_SIZE: u32 = 256;
+
+/// Programs the calling core's MPU with a no-access guard region at `base`,
+/// the low end of that core's `len`-byte stack, and records the bounds for
+/// later inspection via [`core_stack_guard_bounds`].
+///
+/// `base` must be the stack's lowest address (its overflow direction, since
+/// the Cortex-M stack is full-descending) and must be aligned to
+/// [`GUARD_SIZE`] -- a plain 32-byte-aligned [`Stack`][hal] isn't enough on
+/// its own, the caller needs to size/place its stack accordingly. `len`
+/// must be at least `GUARD_SIZE`, since the guard is carved out of the low
+/// end of the stack rather than added on top of it.
+///
+/// Must be called once, by each core, from that core's own entry point,
+/// since the MPU is per-core hardware: core 0 programming its MPU has no
+/// effect on core 1's, and vice versa.
+///
+/// [hal]: https://docs.rs/rp2040-hal/latest/rp2040_hal/multicore/struct.Stack.html
+pub fn arm_core_stack_guard(base: u32, len: u32) {
+    assert_eq!(
+        base % GUARD_SIZE,
+        0,
+        "stack base must be aligned to the guard size ({GUARD_SIZE} bytes)"
+    );
+    assert!(
+        len >= GUARD_SIZE,
+        "stack is smaller than the minimum MPU region size ({GUARD_SIZE} bytes)"
+    );
+
+    STACK_BOUNDS.with(|bounds| bounds.set(Some((base, GUARD_SIZE))));
+
+    // Safety: we only ever touch the MPU registers of the core we're
+    // running on, and only this one dedicated region.
+    let mpu = unsafe { &*MPU::PTR };
+    unsafe {
+        mpu.RNR.write(GUARD_REGION);
+        mpu.RBAR.write(base);
+        // REGION size field is encoded as log2(size) - 1, and bit 0 enables
+        // the region.
+        let size_field = GUARD_SIZE.trailing_zeros() - 1;
+        mpu.RASR.write((size_field << 1) | 1);
+        // Enable the MPU: ENABLE (bit 0), HFNMIENA (bit 1, keeps the MPU
+        // active during fault/NMI handlers so a guard hit there still
+        // faults instead of being silently ignored), and PRIVDEFENA (bit
+        // 2, keeps the default memory map for addresses outside any
+        // region).
+        mpu.CTRL.write(0b111);
+        // Same barriers `cortex_m::peripheral::MPU::enable` uses: DSB
+        // ensures the CTRL write completes before we continue, and ISB
+        // flushes the pipeline so the next instruction fetch is covered by
+        // the now-enabled MPU.
+        cortex_m::asm::dsb();
+        cortex_m::asm::isb();
+    }
+}
+
+/// Returns the calling core's stack guard bounds as `(base, size)`, if
+/// [`arm_core_stack_guard`] has been called on this core.
+pub fn core_stack_guard_bounds() -> Option<(u32, u32)> {
+    STACK_BOUNDS.with(|bounds| bounds.get())
+}
@@ -0,0 +1,46 @@
+//! Shared `.tdata`/`.tbss` copy-init logic for the `TLS_CORE_n` regions.
+//!
+//! Used both by the Cortex-M `__pre_init` hook (`inner::tls_pre_init_hook`)
+//! and by the RISC-V `riscv_init_tp`, since RISC-V has no equivalent hook
+//! to run this once before a core's TLS is first touched.
+
+use core::ptr::{addr_of, addr_of_mut};
+
+use crate::{TLS_CORE_0, TLS_CORE_1};
+
+extern "C" {
+    static __tdata_start: u8;
+    static __tdata_end: u8;
+    static __tbss_start: u8;
+    static __tbss_end: u8;
+}
+
+/// Returns the `TLS_CORE_n` base pointer for `core`.
+fn core_tls_slot(core: usize) -> *mut u8 {
+    match core {
+        0 => unsafe { addr_of_mut!(TLS_CORE_0) },
+        1 => unsafe { addr_of_mut!(TLS_CORE_1) },
+        _ => panic!("core out of range"),
+    }
+}
+
+/// Re-copies the `.tdata`/`.tbss` initial image into `core`'s TLS region,
+/// (re)initialising that core's `#[thread_local]` statics to their startup
+/// values.
+///
+/// # Safety
+///
+/// `core` must not be executing code that could observe its thread-locals
+/// while this runs, since it's about to overwrite them: that means calling
+/// this before that core has started running (at initial boot), or between
+/// [`Core::spawn`][spawn] calls, before the new closure starts running.
+///
+/// [spawn]:
+///     https://docs.rs/rp2040-hal/latest/rp2040_hal/multicore/struct.Core.html#method.spawn
+pub unsafe fn reset_core_tls(core: usize) {
+    let dst = core_tls_slot(core);
+    let datalen = addr_of!(__tdata_end).offset_from(addr_of!(__tdata_start)) as usize;
+    core::ptr::copy(addr_of!(__tdata_start), dst, datalen);
+    let bsslen = addr_of!(__tbss_end).offset_from(addr_of!(__tbss_start)) as usize;
+    dst.add(datalen).write_bytes(0, bsslen);
+}
@@ -0,0 +1,32 @@
+//! `__aeabi_read_tp` for Cortex-M0+ (RP2040).
+//!
+//! Cortex-M0+ only has the 16-bit Thumb instruction subset, so addresses
+//! have to come from a literal pool via `ldr r0, =const` rather than the
+//! `movw`/`movt` pair used on Cortex-M33 (see `cortex_m33.rs`).
+
+use core::arch::global_asm;
+
+use crate::{TLS_CORE_0, TLS_CORE_1};
+
+// Define `__aeabi_read_tp` called by the compiler to get access to
+// thread-local storage.
+global_asm! {
+    ".pushsection .text.__aeabi_read_tp",
+    ".align 4",
+    ".p2align 4,,15",
+    ".global __aeabi_read_tp",
+    ".type __aeabi_read_tp,%function",
+
+    "__aeabi_read_tp:",
+    "    ldr r0, =0xd0000000",      // Load SIO CPUID addr
+    "    ldr r0, [r0]",             // Load CPUID
+    "    cmp r0, #0",               // Check core 0
+    "    ldr r0, ={core_0}",        // Set TLS_CORE_0
+    "    beq 1f",                   // skip if done
+    "    ldr r0, ={core_1}",        // Set TLS_CORE_1
+    "1:  bx lr",
+
+    ".popsection",
+    core_0 = sym TLS_CORE_0,
+    core_1 = sym TLS_CORE_1,
+}
@@ -0,0 +1,34 @@
+//! `__aeabi_read_tp` for Cortex-M33 (RP2350, Arm mode).
+//!
+//! The SIO `CPUID` register is at the same address as on RP2040, but
+//! Cortex-M33 has full 32-bit Thumb-2, so addresses are materialised with
+//! `movw`/`movt` instead of a literal-pool `ldr`, which keeps this routine
+//! out of the pool entirely.
+
+use core::arch::global_asm;
+
+use crate::{TLS_CORE_0, TLS_CORE_1};
+
+global_asm! {
+    ".pushsection .text.__aeabi_read_tp",
+    ".align 4",
+    ".thumb_func",
+    ".global __aeabi_read_tp",
+    ".type __aeabi_read_tp,%function",
+
+    "__aeabi_read_tp:",
+    "    movw r0, #:lower16:0xd0000000", // Load SIO CPUID addr
+    "    movt r0, #:upper16:0xd0000000",
+    "    ldr r0, [r0]",                  // Load CPUID
+    "    cmp r0, #0",                    // Check core 0
+    "    movw r0, #:lower16:{core_0}",   // Set TLS_CORE_0
+    "    movt r0, #:upper16:{core_0}",
+    "    beq 1f",                        // skip if done
+    "    movw r0, #:lower16:{core_1}",   // Set TLS_CORE_1
+    "    movt r0, #:upper16:{core_1}",
+    "1:  bx lr",
+
+    ".popsection",
+    core_0 = sym TLS_CORE_0,
+    core_1 = sym TLS_CORE_1,
+}
@@ -0,0 +1,28 @@
+//! Architecture-specific thread-pointer setup.
+//!
+//! RP2040 only ever runs Cortex-M0+ cores, but RP2350 (Pico 2) can run
+//! either Armv8-M (Cortex-M33) or Hazard3 RISC-V cores, and each needs its
+//! own way of telling the compiler-generated thread-local access code which
+//! `TLS_CORE_n` region the current core should use. Select the right one
+//! with the `rp2350` Cargo feature; on `rp2350` the target's architecture
+//! (`arm` or `riscv32`) picks the Arm vs. RISC-V variant. RP2040 (Cortex-M0+)
+//! is the default, so it's enabled whenever `rp2350` isn't -- this doesn't
+//! depend on any particular default-features setup in a downstream
+//! `Cargo.toml`, so one arch is always selected.
+
+#[cfg(not(feature = "rp2350"))]
+mod cortex_m0;
+
+#[cfg(all(feature = "rp2350", target_arch = "arm"))]
+mod cortex_m33;
+
+#[cfg(all(feature = "rp2350", target_arch = "riscv32"))]
+mod riscv;
+#[cfg(all(feature = "rp2350", target_arch = "riscv32"))]
+pub use riscv::riscv_init_tp;
+
+#[cfg(all(feature = "rp2350", not(any(target_arch = "arm", target_arch = "riscv32"))))]
+compile_error!(
+    "the `rp2350` feature only supports `target_arch = \"arm\"` (Cortex-M33) \
+     or `target_arch = \"riscv32\"` (Hazard3) targets"
+);
@@ -0,0 +1,53 @@
+//! Thread-pointer setup for Hazard3 RISC-V (RP2350, RISC-V mode).
+//!
+//! The Arm `__aeabi_read_tp` ABI works by having the compiler call a
+//! runtime function on every thread-local access. The RISC-V TLS model is
+//! different: the compiler reads thread-locals directly off the `tp`
+//! register (plus a small, fixed offset), with no function call at all. So
+//! instead of a `read_tp`-style routine, this module provides
+//! [`riscv_init_tp`], which each core must call once at startup to point
+//! its own `tp` at the right `TLS_CORE_n` region.
+//!
+//! Hazard3 doesn't number harts via the SIO `CPUID` register the way the
+//! Cortex-M cores do; `mhartid` is the architectural way to ask "which
+//! core am I" on RISC-V, so that's what we read here.
+//!
+//! There's also no `__pre_init`-style hook on RISC-V to copy each core's
+//! `.tdata`/`.tbss` image into its `TLS_CORE_n` region before that core
+//! starts running -- which core a given boot path belongs to is only known
+//! once `mhartid` can actually be read, i.e. once the core is running. So
+//! [`riscv_init_tp`] does that copy-init itself, for its own core, before
+//! pointing `tp` at the result.
+
+use core::arch::asm;
+use core::ptr::addr_of_mut;
+
+use crate::{TLS_CORE_0, TLS_CORE_1};
+
+/// Fixed bias between `tp` and the start of the `.tdata`/`.tbss` block,
+/// defined by the `riscv32*-none-elf` TLS ABI (variant I).
+const TP_TLS_BIAS: usize = 0;
+
+/// Points the calling core's `tp` register at its own TLS region.
+///
+/// Must be called once by each core, before any `#[thread_local]` statics
+/// are accessed on that core -- there is no `__pre_init`-style hook for
+/// this on RISC-V, since `mhartid` (and thus which `TLS_CORE_n` a core
+/// should use) is only known once that core is actually running.
+pub unsafe fn riscv_init_tp() {
+    let hart_id: usize;
+    asm!("csrr {0}, mhartid", out(reg) hart_id);
+
+    // Safety: this core hasn't set its `tp` yet, so it can't have touched a
+    // `#[thread_local]` static, and this is the only core that will ever
+    // pass `hart_id` to `reset_core_tls`.
+    crate::reset_core_tls(hart_id);
+
+    let tls = if hart_id == 0 {
+        addr_of_mut!(TLS_CORE_0)
+    } else {
+        addr_of_mut!(TLS_CORE_1)
+    };
+    let tp = (tls as usize) + TP_TLS_BIAS;
+    asm!("mv tp, {0}", in(reg) tp);
+}